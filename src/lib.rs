@@ -7,38 +7,196 @@ const UPPERCASEOFFSET: u8 = 65;
 const LOWERCASEOFFSET: u8 = 71;
 const DIGITOFFSET: u8 = 4;
 
-#[inline]
-fn index_to_char(index: u8) -> Result<u8, &'static str> {
-    let index = index as u8;
+/// Selects which 64-character symbol set `encode`/`decode` map indices
+/// to. `Standard` and `UrlSafe` only differ in the last two symbols, so
+/// they keep reusing the offset arithmetic below; `Bcrypt` and `Crypt`
+/// permute the whole ordering, so they're driven from a lookup table
+/// instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alphabet {
+    /// RFC 4648 standard alphabet: `A-Za-z0-9+/`
+    #[default]
+    Standard,
+    /// RFC 4648 URL- and filename-safe alphabet: `A-Za-z0-9-_`
+    UrlSafe,
+    /// bcrypt's alphabet: `./A-Za-z0-9`
+    Bcrypt,
+    /// crypt(3)'s alphabet: `./0-9A-Za-z`
+    Crypt,
+}
+
+/// Line ending inserted between wrapped lines by [`encode_wrapped`], and
+/// transparently skipped by [`decode_wrapped`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, as used by PEM.
+    Lf,
+    /// `\r\n`, as used by MIME.
+    CrLf,
+}
+
+/// Structured error returned by [`decode`], pinpointing where and why
+/// decoding failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The byte `byte` at `offset` is not a valid symbol in the selected
+    /// [`Alphabet`].
+    InvalidByte {
+        /// Byte offset into the input at which the invalid byte was found.
+        offset: usize,
+        /// The invalid byte itself.
+        byte: u8,
+    },
+    /// Input or output length did not match the expected,
+    /// padding-adjusted length.
+    InvalidLength,
+    /// The final symbol of the last group, `byte` at `offset`, has
+    /// nonzero bits that get discarded by `combine_bytes` - a real
+    /// encoder never produces this, so the input is truncated or
+    /// corrupted.
+    InvalidLastSymbol {
+        /// Byte offset into the input at which the final symbol was found.
+        offset: usize,
+        /// The final symbol itself.
+        byte: u8,
+    },
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidByte { offset, byte } => {
+                write!(f, "invalid base64 byte {byte:#04x} at offset {offset}")
+            }
+            DecodeError::InvalidLength => write!(f, "invalid input or output length"),
+            DecodeError::InvalidLastSymbol { offset, byte } => write!(
+                f,
+                "nonzero padding bits in final base64 symbol {byte:#04x} at offset {offset}"
+            ),
+        }
+    }
+}
+
+impl LineEnding {
+    #[inline]
+    const fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+
+    #[inline]
+    const fn len(self) -> usize {
+        self.as_bytes().len()
+    }
+}
 
-    let ascii_index = match index {
-        0..=25 => index.saturating_add(UPPERCASEOFFSET), // A-Z
-        26..=51 => index.saturating_add(LOWERCASEOFFSET), // a-z
-        52..=61 => index.saturating_sub(DIGITOFFSET),    // 0-9
-        62 => 43,                                        // +
-        63 => 47,                                        // /
+const BCRYPT_ALPHABET: [u8; 64] =
+    *b"./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const CRYPT_ALPHABET: [u8; 64] =
+    *b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
 
-        _ => return Err("Invalid ascii character index encountered"),
-    } as u8;
+#[inline]
+fn index_to_char(index: u8, alphabet: Alphabet) -> Result<u8, &'static str> {
+    let ascii_index = match alphabet {
+        Alphabet::Standard | Alphabet::UrlSafe => match index {
+            0..=25 => index.saturating_add(UPPERCASEOFFSET), // A-Z
+            26..=51 => index.saturating_add(LOWERCASEOFFSET), // a-z
+            52..=61 => index.saturating_sub(DIGITOFFSET),    // 0-9
+            62 => {
+                if alphabet == Alphabet::UrlSafe {
+                    45 // -
+                } else {
+                    43 // +
+                }
+            }
+            63 => {
+                if alphabet == Alphabet::UrlSafe {
+                    95 // _
+                } else {
+                    47 // /
+                }
+            }
+            _ => return Err("Invalid ascii character index encountered"),
+        },
+        Alphabet::Bcrypt => *BCRYPT_ALPHABET
+            .get(index as usize)
+            .ok_or("Invalid ascii character index encountered")?,
+        Alphabet::Crypt => *CRYPT_ALPHABET
+            .get(index as usize)
+            .ok_or("Invalid ascii character index encountered")?,
+    };
 
     Ok(ascii_index)
 }
 
 #[inline]
-fn char_to_index(c: u8) -> Result<u8, &'static str> {
-    let base64_index = match c {
-        65..=90 => c.saturating_sub(UPPERCASEOFFSET),  // A-Z
-        97..=122 => c.saturating_sub(LOWERCASEOFFSET), // a-z
-        48..=57 => c.saturating_add(DIGITOFFSET),      // 0-9
-        43 => 62,                                      // +
-        47 => 63,                                      // /
+fn char_to_index(c: u8, alphabet: Alphabet) -> Result<u8, &'static str> {
+    let base64_index = match alphabet {
+        Alphabet::Standard | Alphabet::UrlSafe => match c {
+            65..=90 => c.saturating_sub(UPPERCASEOFFSET),  // A-Z
+            97..=122 => c.saturating_sub(LOWERCASEOFFSET), // a-z
+            48..=57 => c.saturating_add(DIGITOFFSET),      // 0-9
+            43 if alphabet == Alphabet::Standard => 62,    // +
+            47 if alphabet == Alphabet::Standard => 63,    // /
+            45 if alphabet == Alphabet::UrlSafe => 62,     // -
+            95 if alphabet == Alphabet::UrlSafe => 63,     // _
 
-        _ => return Err("Invalid base64 char encountered"),
-    } as u8;
+            _ => return Err("Invalid base64 char encountered"),
+        },
+        Alphabet::Bcrypt => BCRYPT_ALPHABET
+            .iter()
+            .position(|&x| x == c)
+            .ok_or("Invalid base64 char encountered")? as u8,
+        Alphabet::Crypt => CRYPT_ALPHABET
+            .iter()
+            .position(|&x| x == c)
+            .ok_or("Invalid base64 char encountered")? as u8,
+    };
 
     Ok(base64_index)
 }
 
+/// Standard-alphabet index-to-char mapping computed with arithmetic and
+/// sign-extension masks instead of a `match`, so the instructions
+/// executed (and their timing) don't depend on `index`. Every value
+/// `0..=63` is valid, so unlike [`index_to_char`] this can't fail.
+///
+/// Used by [`encode_ct`] to avoid leaking secret data through
+/// data-dependent branches.
+#[inline]
+fn index_to_char_ct(index: u8) -> u8 {
+    let x = index as u16;
+    let mut offset: u16 = 0x41;
+    offset = offset.wrapping_add((25u16.wrapping_sub(x) >> 8) & 6); // a-z
+    offset = offset.wrapping_sub((51u16.wrapping_sub(x) >> 8) & 75); // 0-9
+    offset = offset.wrapping_sub((61u16.wrapping_sub(x) >> 8) & 15); // +
+    offset = offset.wrapping_add((62u16.wrapping_sub(x) >> 8) & 3); // /
+    x.wrapping_add(offset) as u8
+}
+
+/// Standard-alphabet char-to-index mapping computed with arithmetic and
+/// sign-extension masks instead of a `match`, so the instructions
+/// executed (and their timing) don't depend on `c`. Returns a negative
+/// value if `c` is not a valid base64 character; callers must fold that
+/// into a single error check *after* processing the whole buffer,
+/// never per-byte, to preserve constant time.
+///
+/// Used by [`decode_ct`] to avoid leaking secret data through
+/// data-dependent branches.
+#[inline]
+fn char_to_index_ct(c: u8) -> i16 {
+    let c = c as i16;
+    let mut ret: i16 = -1;
+    ret += ((0x40 - c) & (c - 0x5b)) >> 8 & (c - 64); // A-Z
+    ret += ((0x60 - c) & (c - 0x7b)) >> 8 & (c - 70); // a-z
+    ret += ((0x2f - c) & (c - 0x3a)) >> 8 & (c + 5); // 0-9
+    ret += ((0x2a - c) & (c - 0x2c)) >> 8 & 63; // +
+    ret += ((0x2e - c) & (c - 0x30)) >> 8 & 64; // /
+    ret
+}
+
 /// This should only be used on a slice known to be of length 3
 #[inline]
 fn split_bytes(chunk: &[u8]) -> [u8; 4] {
@@ -60,75 +218,1067 @@ fn combine_bytes(chunk: &[u8]) -> [u8; 3] {
     ]
 }
 
-/// Encode a base64 encoded slice without padding,
-/// by lookup table.
+/// SIMD fast paths for the unpadded, standard-alphabet inner loops of
+/// [`encode`]/[`decode`]. Each platform's block functions are algebraically
+/// equivalent to [`split_bytes`]/[`combine_bytes`] plus the standard-alphabet
+/// branch of [`index_to_char`]/[`char_to_index`] - they just compute the same
+/// bit shuffling and offset arithmetic across a whole 12-byte (encode) or
+/// 16-byte (decode) block at once instead of one chunk at a time. They're
+/// gated entirely by compile-time `target_feature`, since this crate is
+/// `no_std` and has no way to runtime-detect CPU features without `std`.
+mod simd {
+    /// Process as many whole 12-in/16-out blocks of `data` as fit, writing
+    /// the standard-alphabet encoding of each into `out`. Returns the
+    /// number of 3-byte chunks consumed, which is always a multiple of 4
+    /// and may be 0 if no platform SIMD path is compiled in, `alphabet`
+    /// isn't [`super::Alphabet::Standard`], or `data` is shorter than one
+    /// block. The caller is expected to encode anything left over with the
+    /// scalar loop.
+    #[inline]
+    pub(crate) fn encode_prefix(
+        data: &[u8],
+        out: &mut [u8],
+        nchunks: usize,
+        alphabet: super::Alphabet,
+    ) -> usize {
+        #[cfg(all(target_arch = "x86_64", target_feature = "ssse3"))]
+        {
+            if alphabet != super::Alphabet::Standard {
+                return 0;
+            }
+            let nblocks = nchunks / 4;
+            for b in 0..nblocks {
+                let chunk: &[u8; 12] = data[12 * b..12 * b + 12].try_into().unwrap();
+                let encoded = unsafe { x86::encode_block(chunk) };
+                out[16 * b..16 * b + 16].copy_from_slice(&encoded);
+            }
+            nblocks * 4
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if alphabet != super::Alphabet::Standard {
+                return 0;
+            }
+            let nblocks = nchunks / 4;
+            for b in 0..nblocks {
+                let chunk: &[u8; 12] = data[12 * b..12 * b + 12].try_into().unwrap();
+                let encoded = unsafe { aarch64::encode_block(chunk) };
+                out[16 * b..16 * b + 16].copy_from_slice(&encoded);
+            }
+            nblocks * 4
+        }
+
+        #[cfg(not(any(
+            all(target_arch = "x86_64", target_feature = "ssse3"),
+            all(target_arch = "aarch64", target_feature = "neon")
+        )))]
+        {
+            let _ = (data, out, nchunks, alphabet);
+            0
+        }
+    }
+
+    /// Process as many whole 16-in/12-out blocks of `data` as fit, writing
+    /// the standard-alphabet decoding of each into `out`. Returns the
+    /// number of 4-byte chunks consumed. Stops at (and does not consume)
+    /// the first block containing a byte outside the standard alphabet,
+    /// so the caller's scalar loop can take over and report the precise
+    /// offset - the vectorized check only tells us a block is bad, not
+    /// which byte in it.
+    #[inline]
+    pub(crate) fn decode_prefix(
+        data: &[u8],
+        out: &mut [u8],
+        nchunks: usize,
+        alphabet: super::Alphabet,
+    ) -> usize {
+        #[cfg(all(target_arch = "x86_64", target_feature = "ssse3"))]
+        {
+            if alphabet != super::Alphabet::Standard {
+                return 0;
+            }
+            let mut start = 0;
+            while start + 4 <= nchunks {
+                let block: &[u8; 16] = data[4 * start..4 * start + 16].try_into().unwrap();
+                match unsafe { x86::decode_block(block) } {
+                    Some(bytes) => {
+                        out[3 * start..3 * start + 12].copy_from_slice(&bytes);
+                        start += 4;
+                    }
+                    None => break,
+                }
+            }
+            start
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if alphabet != super::Alphabet::Standard {
+                return 0;
+            }
+            let mut start = 0;
+            while start + 4 <= nchunks {
+                let block: &[u8; 16] = data[4 * start..4 * start + 16].try_into().unwrap();
+                match unsafe { aarch64::decode_block(block) } {
+                    Some(bytes) => {
+                        out[3 * start..3 * start + 12].copy_from_slice(&bytes);
+                        start += 4;
+                    }
+                    None => break,
+                }
+            }
+            start
+        }
+
+        #[cfg(not(any(
+            all(target_arch = "x86_64", target_feature = "ssse3"),
+            all(target_arch = "aarch64", target_feature = "neon")
+        )))]
+        {
+            let _ = (data, out, nchunks, alphabet);
+            0
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "ssse3"))]
+    mod x86 {
+        use core::arch::x86_64::*;
+
+        /// Encode one 12-byte chunk (4 groups of 3 bytes) to 16 standard-
+        /// alphabet ASCII bytes. Mirrors [`super::super::split_bytes`] plus
+        /// the standard-alphabet arithmetic from
+        /// [`super::super::index_to_char_ct`], vectorized: a `pshufb`
+        /// regroups the input bytes so each 16-bit lane straddles a 6-bit
+        /// boundary, then masked multiplies pull the two 6-bit fields out
+        /// of each lane (the same trick `mulhi`/`mullo` use to extract
+        /// high/low halves of a product), and a chain of signed byte
+        /// compares adds the right ASCII offset to each resulting index.
+        #[target_feature(enable = "ssse3")]
+        pub(super) unsafe fn encode_block(data: &[u8; 12]) -> [u8; 16] {
+            let mut buf = [0_u8; 16];
+            buf[..12].copy_from_slice(data);
+            let in_ = _mm_loadu_si128(buf.as_ptr() as *const __m128i);
+
+            let shuf = _mm_setr_epi8(1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10);
+            let in_ = _mm_shuffle_epi8(in_, shuf);
+            let t0 = _mm_and_si128(in_, _mm_set1_epi32(0x0FC0FC00_u32 as i32));
+            let t1 = _mm_mulhi_epu16(t0, _mm_set1_epi32(0x04000040_u32 as i32));
+            let t2 = _mm_and_si128(in_, _mm_set1_epi32(0x003F03F0_u32 as i32));
+            let t3 = _mm_mullo_epi16(t2, _mm_set1_epi32(0x01000010_u32 as i32));
+            let indices = _mm_or_si128(t1, t3);
+
+            let mut base = _mm_set1_epi8(65);
+            let gt25 = _mm_cmpgt_epi8(indices, _mm_set1_epi8(25));
+            base = _mm_add_epi8(base, _mm_and_si128(gt25, _mm_set1_epi8(6)));
+            let gt51 = _mm_cmpgt_epi8(indices, _mm_set1_epi8(51));
+            base = _mm_sub_epi8(base, _mm_and_si128(gt51, _mm_set1_epi8(75)));
+            let gt61 = _mm_cmpgt_epi8(indices, _mm_set1_epi8(61));
+            base = _mm_sub_epi8(base, _mm_and_si128(gt61, _mm_set1_epi8(15)));
+            let gt62 = _mm_cmpgt_epi8(indices, _mm_set1_epi8(62));
+            base = _mm_add_epi8(base, _mm_and_si128(gt62, _mm_set1_epi8(3)));
+            let ascii = _mm_add_epi8(indices, base);
+
+            let mut out = [0_u8; 16];
+            _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, ascii);
+            out
+        }
+
+        /// Decode one 16-byte chunk (4 groups of 4 standard-alphabet
+        /// characters) to 12 bytes, or `None` if any of the 16 characters
+        /// is outside the standard alphabet. Mirrors the standard-alphabet
+        /// arithmetic from [`super::super::char_to_index_ct`] to compute
+        /// indices, checks for any invalid lane via `pmovmskb` on the sign
+        /// bit (invalid lanes are left negative), then undoes the encode
+        /// side's `pshufb`/masked-multiply trick with `pmaddubsw`/`pmaddwd`
+        /// to repack the 4x6 bits into 3 bytes per group, matching
+        /// [`super::super::combine_bytes`].
+        #[target_feature(enable = "ssse3")]
+        pub(super) unsafe fn decode_block(data: &[u8; 16]) -> Option<[u8; 12]> {
+            let c = _mm_loadu_si128(data.as_ptr() as *const __m128i);
+            let mut indices = _mm_set1_epi8(-1);
+
+            let az_lo = _mm_cmpgt_epi8(c, _mm_set1_epi8(64));
+            let az_hi = _mm_cmplt_epi8(c, _mm_set1_epi8(91));
+            let mask_az = _mm_and_si128(az_lo, az_hi);
+            indices = _mm_add_epi8(indices, _mm_and_si128(mask_az, _mm_sub_epi8(c, _mm_set1_epi8(64))));
+
+            let lz_lo = _mm_cmpgt_epi8(c, _mm_set1_epi8(96));
+            let lz_hi = _mm_cmplt_epi8(c, _mm_set1_epi8(123));
+            let mask_lz = _mm_and_si128(lz_lo, lz_hi);
+            indices = _mm_add_epi8(indices, _mm_and_si128(mask_lz, _mm_sub_epi8(c, _mm_set1_epi8(70))));
+
+            let dg_lo = _mm_cmpgt_epi8(c, _mm_set1_epi8(47));
+            let dg_hi = _mm_cmplt_epi8(c, _mm_set1_epi8(58));
+            let mask_dg = _mm_and_si128(dg_lo, dg_hi);
+            indices = _mm_add_epi8(indices, _mm_and_si128(mask_dg, _mm_add_epi8(c, _mm_set1_epi8(5))));
+
+            let mask_plus = _mm_cmpeq_epi8(c, _mm_set1_epi8(43));
+            indices = _mm_add_epi8(indices, _mm_and_si128(mask_plus, _mm_set1_epi8(63)));
+
+            let mask_slash = _mm_cmpeq_epi8(c, _mm_set1_epi8(47));
+            indices = _mm_add_epi8(indices, _mm_and_si128(mask_slash, _mm_set1_epi8(64)));
+
+            if _mm_movemask_epi8(indices) != 0 {
+                return None;
+            }
+
+            let merge_ab_and_bc = _mm_maddubs_epi16(indices, _mm_set1_epi32(0x01400140_u32 as i32));
+            let merged = _mm_madd_epi16(merge_ab_and_bc, _mm_set1_epi32(0x00011000_u32 as i32));
+            let shuf = _mm_setr_epi8(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1);
+            let combined = _mm_shuffle_epi8(merged, shuf);
+
+            let mut buf = [0_u8; 16];
+            _mm_storeu_si128(buf.as_mut_ptr() as *mut __m128i, combined);
+            let mut out = [0_u8; 12];
+            out.copy_from_slice(&buf[..12]);
+            Some(out)
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    mod aarch64 {
+        use core::arch::aarch64::*;
+
+        /// Encode one 12-byte chunk, NEON equivalent of `x86::encode_block`.
+        /// NEON has no direct equivalent of the `mulhi`/`mullo` trick the
+        /// SSSE3 path uses to pull 6-bit fields out of regrouped 16-bit
+        /// lanes, so this instead gathers each output lane's two
+        /// contributing source bytes directly with `tbl` and combines them
+        /// with per-lane variable shifts (`vshlq_u8`, negative = right
+        /// shift) and masks - algebraically the same split as
+        /// [`super::super::split_bytes`], just without the intermediate
+        /// 16-bit repacking step.
+        #[target_feature(enable = "neon")]
+        pub(super) unsafe fn encode_block(data: &[u8; 12]) -> [u8; 16] {
+            let mut buf = [0_u8; 16];
+            buf[..12].copy_from_slice(data);
+            let in_ = vld1q_u8(buf.as_ptr());
+
+            let high_idx: [u8; 16] = [0, 0, 1, 2, 3, 3, 4, 5, 6, 6, 7, 8, 9, 9, 10, 11];
+            let low_idx: [u8; 16] = [0, 1, 2, 2, 3, 4, 5, 5, 6, 7, 8, 8, 9, 10, 11, 11];
+            let high_src = vqtbl1q_u8(in_, vld1q_u8(high_idx.as_ptr()));
+            let low_src = vqtbl1q_u8(in_, vld1q_u8(low_idx.as_ptr()));
+
+            let high_shift: [i8; 16] = [-2, 4, 2, 0, -2, 4, 2, 0, -2, 4, 2, 0, -2, 4, 2, 0];
+            let low_shift: [i8; 16] = [0, -4, -6, 0, 0, -4, -6, 0, 0, -4, -6, 0, 0, -4, -6, 0];
+            let high_mask: [u8; 16] = [
+                0x3F, 0x30, 0x3C, 0x3F, 0x3F, 0x30, 0x3C, 0x3F, 0x3F, 0x30, 0x3C, 0x3F, 0x3F, 0x30,
+                0x3C, 0x3F,
+            ];
+            let low_mask: [u8; 16] = [
+                0x00, 0x0F, 0x03, 0x00, 0x00, 0x0F, 0x03, 0x00, 0x00, 0x0F, 0x03, 0x00, 0x00, 0x0F,
+                0x03, 0x00,
+            ];
+
+            let high = vandq_u8(
+                vshlq_u8(high_src, vld1q_s8(high_shift.as_ptr())),
+                vld1q_u8(high_mask.as_ptr()),
+            );
+            let low = vandq_u8(
+                vshlq_u8(low_src, vld1q_s8(low_shift.as_ptr())),
+                vld1q_u8(low_mask.as_ptr()),
+            );
+            let indices = vorrq_u8(high, low);
+
+            let mut base = vdupq_n_u8(65);
+            base = vaddq_u8(base, vandq_u8(vcgtq_u8(indices, vdupq_n_u8(25)), vdupq_n_u8(6)));
+            base = vsubq_u8(base, vandq_u8(vcgtq_u8(indices, vdupq_n_u8(51)), vdupq_n_u8(75)));
+            base = vsubq_u8(base, vandq_u8(vcgtq_u8(indices, vdupq_n_u8(61)), vdupq_n_u8(15)));
+            base = vaddq_u8(base, vandq_u8(vcgtq_u8(indices, vdupq_n_u8(62)), vdupq_n_u8(3)));
+            let ascii = vaddq_u8(indices, base);
+
+            let mut out = [0_u8; 16];
+            vst1q_u8(out.as_mut_ptr(), ascii);
+            out
+        }
+
+        /// Decode one 16-byte chunk, NEON equivalent of `x86::decode_block`.
+        /// Invalid lanes are left with the top bit set (mirroring the
+        /// SSSE3 path's `-1` sentinel), so `vmaxvq_u8` - a horizontal max
+        /// across all 16 lanes - doubles as the "any lane invalid" check in
+        /// place of `pmovmskb`. The repacking step again uses `tbl` plus
+        /// per-lane shifts instead of a widening multiply, mirroring
+        /// [`super::super::combine_bytes`].
+        #[target_feature(enable = "neon")]
+        pub(super) unsafe fn decode_block(data: &[u8; 16]) -> Option<[u8; 12]> {
+            let c = vld1q_u8(data.as_ptr());
+            let mut indices = vdupq_n_u8(0xFF);
+
+            let mask_az = vandq_u8(vcgtq_u8(c, vdupq_n_u8(64)), vcltq_u8(c, vdupq_n_u8(91)));
+            indices = vaddq_u8(indices, vandq_u8(mask_az, vsubq_u8(c, vdupq_n_u8(64))));
+
+            let mask_lz = vandq_u8(vcgtq_u8(c, vdupq_n_u8(96)), vcltq_u8(c, vdupq_n_u8(123)));
+            indices = vaddq_u8(indices, vandq_u8(mask_lz, vsubq_u8(c, vdupq_n_u8(70))));
+
+            let mask_dg = vandq_u8(vcgtq_u8(c, vdupq_n_u8(47)), vcltq_u8(c, vdupq_n_u8(58)));
+            indices = vaddq_u8(indices, vandq_u8(mask_dg, vaddq_u8(c, vdupq_n_u8(5))));
+
+            let mask_plus = vceqq_u8(c, vdupq_n_u8(43));
+            indices = vaddq_u8(indices, vandq_u8(mask_plus, vdupq_n_u8(63)));
+
+            let mask_slash = vceqq_u8(c, vdupq_n_u8(47));
+            indices = vaddq_u8(indices, vandq_u8(mask_slash, vdupq_n_u8(64)));
+
+            if vmaxvq_u8(vandq_u8(indices, vdupq_n_u8(0x80))) != 0 {
+                return None;
+            }
+
+            let high_idx: [u8; 16] = [0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13, 14, 0, 0, 0, 0];
+            let low_idx: [u8; 16] = [1, 2, 3, 5, 6, 7, 9, 10, 11, 13, 14, 15, 0, 0, 0, 0];
+            let high_src = vqtbl1q_u8(indices, vld1q_u8(high_idx.as_ptr()));
+            let low_src = vqtbl1q_u8(indices, vld1q_u8(low_idx.as_ptr()));
+
+            let high_shift: [i8; 16] = [2, 4, 6, 2, 4, 6, 2, 4, 6, 2, 4, 6, 0, 0, 0, 0];
+            let low_shift: [i8; 16] = [-4, -2, 0, -4, -2, 0, -4, -2, 0, -4, -2, 0, 0, 0, 0, 0];
+            let high_mask: [u8; 16] = [
+                0xFC, 0xF0, 0xC0, 0xFC, 0xF0, 0xC0, 0xFC, 0xF0, 0xC0, 0xFC, 0xF0, 0xC0, 0, 0, 0, 0,
+            ];
+            let low_mask: [u8; 16] = [
+                0x0F, 0x3F, 0x3F, 0x0F, 0x3F, 0x3F, 0x0F, 0x3F, 0x3F, 0x0F, 0x3F, 0x3F, 0, 0, 0, 0,
+            ];
+
+            let high = vandq_u8(
+                vshlq_u8(high_src, vld1q_s8(high_shift.as_ptr())),
+                vld1q_u8(high_mask.as_ptr()),
+            );
+            let low = vandq_u8(
+                vshlq_u8(low_src, vld1q_s8(low_shift.as_ptr())),
+                vld1q_u8(low_mask.as_ptr()),
+            );
+            let combined = vorrq_u8(high, low);
+
+            let mut buf = [0_u8; 16];
+            vst1q_u8(buf.as_mut_ptr(), combined);
+            let mut out = [0_u8; 12];
+            out.copy_from_slice(&buf[..12]);
+            Some(out)
+        }
+    }
+}
+
+/// Compute the exact output buffer length `encode` needs for an input
+/// of `input_len` bytes.
 ///
-/// Input length must be a multiple of 3 bytes.
-/// Output length must be exactly 4/3 of input length.
+/// When `padded` is `false`, `input_len` must be a multiple of 3 -
+/// the result is simply `4 * input_len / 3`. When `padded` is `true`,
+/// any input length is allowed and the result accounts for the one or
+/// two trailing `=` characters used to pad the final group, per RFC 4648.
+#[inline]
+pub const fn encoded_len(input_len: usize, padded: bool) -> usize {
+    if padded {
+        4 * ((input_len + 2) / 3)
+    } else {
+        input_len * 4 / 3
+    }
+}
+
+/// Compute the exact output buffer length `decode` needs for the given
+/// (still encoded) `input`.
+///
+/// When `padded` is `false`, this is `3 * input.len() / 4`. When `padded`
+/// is `true`, the trailing `=` characters in `input` are inspected so the
+/// returned length excludes the 1 or 2 bytes represented by the padding.
+///
+/// # Errors
+/// * If `input` length is not a multiple of 4 bytes
+#[inline]
+pub fn decoded_len(input: &[u8], padded: bool) -> Result<usize, &'static str> {
+    let nin = input.len();
+
+    if nin % 4 != 0 {
+        return Err("Input data must be a multiple of 4 bytes");
+    } else if !padded {
+        return Ok(nin * 3 / 4);
+    }
+
+    let nblocks = nin / 4;
+    let pad = if nin == 0 {
+        0
+    } else if input[nin - 1] == b'=' {
+        if input[nin - 2] == b'=' {
+            2
+        } else {
+            1
+        }
+    } else {
+        0
+    };
+
+    Ok(3 * nblocks - pad)
+}
+
+/// Compute the exact output buffer length `encode_wrapped` needs, given
+/// the *unwrapped* encoded length (see [`encoded_len`]) and the
+/// `line_length`/`line_ending` it will be wrapped with.
+///
+/// A line break is inserted after every `line_length` output characters,
+/// including after a shorter final line, matching typical PEM/MIME
+/// formatting (64 or 76 characters per line, respectively).
+///
+/// # Panics
+/// * If `line_length` is 0
+#[inline]
+pub const fn wrapped_encoded_len(
+    encoded_len: usize,
+    line_length: usize,
+    line_ending: LineEnding,
+) -> usize {
+    let num_lines = (encoded_len + line_length - 1) / line_length;
+    encoded_len + num_lines * line_ending.len()
+}
+
+/// Encode a base64 encoded slice by lookup table.
+///
+/// When `padded` is `false`, input length must be a multiple of 3 bytes
+/// and output length must be exactly 4/3 of input length, matching the
+/// original unpadded behavior of this crate. When `padded` is `true`,
+/// any input length is allowed; a trailing 1- or 2-byte group is encoded
+/// with one or two `=` characters per RFC 4648. Use [`encoded_len`] to
+/// size `out` correctly for either mode. `alphabet` selects which 64
+/// symbols indices are mapped to - see [`Alphabet`].
 ///
 /// # Errors
-/// * If input length is not a multiple of 3 bytes
-/// * If output length is not exactly 4/3 of input length
+/// * If `padded` is `false` and input length is not a multiple of 3 bytes
+/// * If output length does not match [`encoded_len`] for `data` and `padded`
 /// * If any invalid base64 characters are encountered
 #[inline]
-pub fn encode(data: &[u8], out: &mut [u8]) -> Result<(), &'static str> {
+pub fn encode(
+    data: &[u8],
+    out: &mut [u8],
+    padded: bool,
+    alphabet: Alphabet,
+) -> Result<(), &'static str> {
     let nin = data.len();
-    let nout = out.len();
+
+    if !padded {
+        let nchunks = nin / 3;
+        if nin % 3 != 0 {
+            return Err("Input data must be a multiple of 3 bytes");
+        } else if out.len() != encoded_len(nin, false) {
+            return Err("Output data length should be 4/3 input data length");
+        }
+
+        let start = simd::encode_prefix(data, out, nchunks, alphabet);
+        for j in start..nchunks {
+            let d = &data[3 * j..3 * j + 3];
+            let o = &mut out[4 * j..4 * j + 4];
+            let expanded = split_bytes(d);
+            for i in 0..4 {
+                o[i] = index_to_char(expanded[i], alphabet)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    if out.len() != encoded_len(nin, true) {
+        return Err("Output data length should match encoded_len(data.len(), true)");
+    }
+
     let nchunks = nin / 3;
+    let remainder = nin % 3;
 
-    if nin % 3 != 0 {
-        return Err("Input data must be a multiple of 3 bytes");
-    } else if nout != (nin * 4 / 3) {
-        return Err("Output data length should be 4/3 input data length");
+    let start = simd::encode_prefix(data, out, nchunks, alphabet);
+    for j in start..nchunks {
+        let d = &data[3 * j..3 * j + 3];
+        let o = &mut out[4 * j..4 * j + 4];
+        let expanded = split_bytes(d);
+        for i in 0..4 {
+            o[i] = index_to_char(expanded[i], alphabet)?;
+        }
+    }
+
+    // Encode the trailing 1- or 2-byte group, padding with `=` as needed.
+    let o = &mut out[4 * nchunks..];
+    match remainder {
+        0 => {}
+        1 => {
+            let b = data[nin - 1];
+            o[0] = index_to_char(b >> 2, alphabet)?;
+            o[1] = index_to_char((b & 0b00000011) << 4, alphabet)?;
+            o[2] = b'=';
+            o[3] = b'=';
+        }
+        2 => {
+            let b0 = data[nin - 2];
+            let b1 = data[nin - 1];
+            o[0] = index_to_char(b0 >> 2, alphabet)?;
+            o[1] = index_to_char((b0 & 0b00000011) << 4 | b1 >> 4, alphabet)?;
+            o[2] = index_to_char((b1 & 0b00001111) << 2, alphabet)?;
+            o[3] = b'=';
+        }
+        _ => unreachable!("remainder of division by 3 is always 0, 1, or 2"),
+    }
+
+    Ok(())
+}
+
+/// Decode a base64 encoded slice by lookup table.
+///
+/// When `padded` is `false`, input length must be a multiple of 4 bytes
+/// and output length must be exactly 3/4 of input length, matching the
+/// original unpadded behavior of this crate. When `padded` is `true`,
+/// up to two trailing `=` characters are accepted and the final 1 or 2
+/// output bytes are reconstructed from the last group; the unused low
+/// bits of the last symbol are validated to be zero, since a nonzero
+/// value there means the input was produced by something other than a
+/// real encoder. Use [`decoded_len`] to size `out` correctly for either
+/// mode. `alphabet` selects which 64 symbols indices are mapped to -
+/// see [`Alphabet`].
+///
+/// Unlike [`decode_ct`], `decode` reports failures as a [`DecodeError`]
+/// carrying the offset and byte involved, rather than a bare
+/// `&'static str`.
+///
+/// # Errors
+/// * [`DecodeError::InvalidLength`] if input or output length don't match
+///   the expected, padding-adjusted lengths
+/// * [`DecodeError::InvalidByte`] if any invalid base64 characters are
+///   encountered
+/// * [`DecodeError::InvalidLastSymbol`] if `padded` is `true` and the
+///   unused bits of the final symbol are nonzero
+#[inline]
+pub fn decode(
+    data: &[u8],
+    out: &mut [u8],
+    padded: bool,
+    alphabet: Alphabet,
+) -> Result<(), DecodeError> {
+    let nin = data.len();
+
+    if nin % 4 != 0 {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    if !padded {
+        if out.len() != nin * 3 / 4 {
+            return Err(DecodeError::InvalidLength);
+        }
+
+        let nchunks = nin / 4;
+        let start = simd::decode_prefix(data, out, nchunks, alphabet);
+        let mut converted = [0_u8; 4];
+        for j in start..nchunks {
+            let d = &data[4 * j..4 * j + 4];
+            let o = &mut out[3 * j..3 * j + 3];
+
+            // Invert character mapping
+            for i in 0..4 {
+                let offset = 4 * j + i;
+                converted[i] = char_to_index(d[i], alphabet)
+                    .map_err(|_| DecodeError::InvalidByte { offset, byte: d[i] })?;
+            }
+
+            // Recombine 4 expanded bytes back to 3
+            let combined: [u8; 3] = combine_bytes(&converted);
+            o.copy_from_slice(&combined);
+        }
+
+        return Ok(());
+    }
+
+    let nblocks = nin / 4;
+    let pad = if nin == 0 {
+        0
+    } else if data[nin - 1] == b'=' {
+        if data[nin - 2] == b'=' {
+            2
+        } else {
+            1
+        }
     } else {
+        0
+    };
+
+    if out.len() != 3 * nblocks - pad {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let full_blocks = nblocks - if pad == 0 { 0 } else { 1 };
+    let start = simd::decode_prefix(data, out, full_blocks, alphabet);
+    let mut converted = [0_u8; 4];
+    for j in start..full_blocks {
+        let d = &data[4 * j..4 * j + 4];
+        let o = &mut out[3 * j..3 * j + 3];
+
+        for i in 0..4 {
+            let offset = 4 * j + i;
+            converted[i] = char_to_index(d[i], alphabet)
+                .map_err(|_| DecodeError::InvalidByte { offset, byte: d[i] })?;
+        }
+
+        let combined: [u8; 3] = combine_bytes(&converted);
+        o.copy_from_slice(&combined);
+    }
+
+    // Decode the trailing, padded group.
+    if pad != 0 {
+        let base_offset = 4 * full_blocks;
+        let d = &data[base_offset..base_offset + 4];
+        let o = &mut out[3 * full_blocks..];
+
+        let c0 = char_to_index(d[0], alphabet).map_err(|_| DecodeError::InvalidByte {
+            offset: base_offset,
+            byte: d[0],
+        })?;
+        let c1 = char_to_index(d[1], alphabet).map_err(|_| DecodeError::InvalidByte {
+            offset: base_offset + 1,
+            byte: d[1],
+        })?;
+
+        if pad == 1 {
+            let c2 = char_to_index(d[2], alphabet).map_err(|_| DecodeError::InvalidByte {
+                offset: base_offset + 2,
+                byte: d[2],
+            })?;
+            if c2 & 0b00000011 != 0 {
+                return Err(DecodeError::InvalidLastSymbol {
+                    offset: base_offset + 2,
+                    byte: d[2],
+                });
+            }
+            let combined = combine_bytes(&[c0, c1, c2, 0]);
+            o[0] = combined[0];
+            o[1] = combined[1];
+        } else {
+            if c1 & 0b00001111 != 0 {
+                return Err(DecodeError::InvalidLastSymbol {
+                    offset: base_offset + 1,
+                    byte: d[1],
+                });
+            }
+            let combined = combine_bytes(&[c0, c1, 0, 0]);
+            o[0] = combined[0];
+        }
+    }
+
+    Ok(())
+}
+
+/// Constant-time, branchless equivalent of [`encode`] for the standard
+/// alphabet, suitable for encoding secret data such as key material.
+/// `index_to_char_ct` computes every symbol with arithmetic and
+/// sign-extension masks rather than a `match`, so execution time does
+/// not depend on the value of `data`.
+///
+/// # Errors
+/// * If `padded` is `false` and input length is not a multiple of 3 bytes
+/// * If output length does not match [`encoded_len`] for `data` and `padded`
+#[inline]
+pub fn encode_ct(data: &[u8], out: &mut [u8], padded: bool) -> Result<(), &'static str> {
+    let nin = data.len();
+
+    if !padded {
+        let nchunks = nin / 3;
+        if nin % 3 != 0 {
+            return Err("Input data must be a multiple of 3 bytes");
+        } else if out.len() != encoded_len(nin, false) {
+            return Err("Output data length should be 4/3 input data length");
+        }
+
         for j in 0..nchunks {
             let d = &data[3 * j..3 * j + 3];
             let o = &mut out[4 * j..4 * j + 4];
             let expanded = split_bytes(d);
             for i in 0..4 {
-                o[i] = index_to_char(expanded[i])?;
+                o[i] = index_to_char_ct(expanded[i]);
             }
         }
+
+        return Ok(());
+    }
+
+    if out.len() != encoded_len(nin, true) {
+        return Err("Output data length should match encoded_len(data.len(), true)");
+    }
+
+    let nchunks = nin / 3;
+    let remainder = nin % 3;
+
+    for j in 0..nchunks {
+        let d = &data[3 * j..3 * j + 3];
+        let o = &mut out[4 * j..4 * j + 4];
+        let expanded = split_bytes(d);
+        for i in 0..4 {
+            o[i] = index_to_char_ct(expanded[i]);
+        }
+    }
+
+    // Encode the trailing 1- or 2-byte group, padding with `=` as needed.
+    let o = &mut out[4 * nchunks..];
+    match remainder {
+        0 => {}
+        1 => {
+            let b = data[nin - 1];
+            o[0] = index_to_char_ct(b >> 2);
+            o[1] = index_to_char_ct((b & 0b00000011) << 4);
+            o[2] = b'=';
+            o[3] = b'=';
+        }
+        2 => {
+            let b0 = data[nin - 2];
+            let b1 = data[nin - 1];
+            o[0] = index_to_char_ct(b0 >> 2);
+            o[1] = index_to_char_ct((b0 & 0b00000011) << 4 | b1 >> 4);
+            o[2] = index_to_char_ct((b1 & 0b00001111) << 2);
+            o[3] = b'=';
+        }
+        _ => unreachable!("remainder of division by 3 is always 0, 1, or 2"),
     }
 
     Ok(())
 }
 
-/// Decode a base64 encoded slice without padding,
-/// by lookup table.
-///
-/// Input length must be a multiple of 4 bytes.
-/// Output length must be exactly 3/4 of input length.
+/// Constant-time, branchless equivalent of [`decode`] for the standard
+/// alphabet, suitable for decoding secret data such as key material.
+/// `char_to_index_ct` computes every symbol's index with arithmetic and
+/// sign-extension masks rather than a `match`, and invalid characters
+/// are folded into a single error flag that is only checked once the
+/// entire buffer has been processed, so execution time does not depend
+/// on the value of `data`.
 ///
 /// # Errors
 /// * If input length is not a multiple of 4 bytes
-/// * If output length is not exactly 3/4 of input length
+/// * If output length does not match [`decoded_len`] for `data` and `padded`
 /// * If any invalid base64 characters are encountered
+/// * If `padded` is `true` and the unused bits of the final symbol are nonzero
 #[inline]
-pub fn decode(data: &[u8], out: &mut [u8]) -> Result<(), &'static str> {
+pub fn decode_ct(data: &[u8], out: &mut [u8], padded: bool) -> Result<(), &'static str> {
     let nin = data.len();
-    let nout = out.len();
-    let nchunks = nin / 4;
 
     if nin % 4 != 0 {
         return Err("Input data must be a multiple of 4 bytes");
-    } else if nout != (nin * 3 / 4) {
-        return Err("Output data length should be 3/4 input data length");
-    } else {
+    }
+
+    let mut bad: i16 = 0;
+    // Tracked separately from `bad` so a nonzero-padding-bits failure is
+    // reported distinctly from an invalid character, matching the wording
+    // `decode`'s `DecodeError::InvalidLastSymbol` uses for the same
+    // condition; checked only once, after the whole buffer is processed,
+    // to keep this path branchless on the value of `data`.
+    let mut bad_pad: i16 = 0;
+
+    if !padded {
+        if out.len() != nin * 3 / 4 {
+            return Err("Output data length should be 3/4 input data length");
+        }
+
+        let nchunks = nin / 4;
         let mut converted = [0_u8; 4];
         for j in 0..nchunks {
             let d = &data[4 * j..4 * j + 4];
             let o = &mut out[3 * j..3 * j + 3];
 
-            // Invert character mapping
             for i in 0..4 {
-                converted[i] = char_to_index(d[i])?;
+                let v = char_to_index_ct(d[i]);
+                bad |= v;
+                converted[i] = v as u8;
             }
 
-            // Recombine 4 expanded bytes back to 3
             let combined: [u8; 3] = combine_bytes(&converted);
             o.copy_from_slice(&combined);
         }
+
+        return if bad < 0 {
+            Err("Invalid base64 char encountered")
+        } else {
+            Ok(())
+        };
+    }
+
+    let nblocks = nin / 4;
+    let pad = if nin == 0 {
+        0
+    } else if data[nin - 1] == b'=' {
+        if data[nin - 2] == b'=' {
+            2
+        } else {
+            1
+        }
+    } else {
+        0
+    };
+
+    if out.len() != 3 * nblocks - pad {
+        return Err("Output data length should match decoded_len(data, true)");
+    }
+
+    let full_blocks = nblocks - if pad == 0 { 0 } else { 1 };
+    let mut converted = [0_u8; 4];
+    for j in 0..full_blocks {
+        let d = &data[4 * j..4 * j + 4];
+        let o = &mut out[3 * j..3 * j + 3];
+
+        for i in 0..4 {
+            let v = char_to_index_ct(d[i]);
+            bad |= v;
+            converted[i] = v as u8;
+        }
+
+        let combined: [u8; 3] = combine_bytes(&converted);
+        o.copy_from_slice(&combined);
+    }
+
+    // Decode the trailing, padded group.
+    if pad != 0 {
+        let d = &data[4 * full_blocks..4 * full_blocks + 4];
+        let o = &mut out[3 * full_blocks..];
+
+        let c0 = char_to_index_ct(d[0]);
+        let c1 = char_to_index_ct(d[1]);
+        bad |= c0;
+        bad |= c1;
+
+        if pad == 1 {
+            let c2 = char_to_index_ct(d[2]);
+            bad |= c2;
+            bad_pad |= (c2 & 0b00000011).wrapping_neg();
+            let combined = combine_bytes(&[c0 as u8, c1 as u8, c2 as u8, 0]);
+            o[0] = combined[0];
+            o[1] = combined[1];
+        } else {
+            bad_pad |= (c1 & 0b00001111).wrapping_neg();
+            let combined = combine_bytes(&[c0 as u8, c1 as u8, 0, 0]);
+            o[0] = combined[0];
+        }
+    }
+
+    if bad < 0 {
+        Err("Invalid base64 char encountered")
+    } else if bad_pad < 0 {
+        Err("nonzero padding bits in final base64 symbol")
+    } else {
+        Ok(())
+    }
+}
+
+/// Write `b` into `out` at `*out_idx`, inserting `line_ending` whenever
+/// `*col` reaches `line_length`. Used by [`encode_wrapped`] to interleave
+/// line breaks into the encoded output as it's produced.
+#[inline]
+fn put_wrapped(
+    out: &mut [u8],
+    out_idx: &mut usize,
+    col: &mut usize,
+    b: u8,
+    line_length: usize,
+    line_ending: LineEnding,
+) {
+    out[*out_idx] = b;
+    *out_idx += 1;
+    *col += 1;
+
+    if *col == line_length {
+        let ending = line_ending.as_bytes();
+        out[*out_idx..*out_idx + ending.len()].copy_from_slice(ending);
+        *out_idx += ending.len();
+        *col = 0;
+    }
+}
+
+/// Encode a base64 slice by lookup table, inserting `line_ending` every
+/// `line_length` output characters, for embedding in PEM or MIME bodies.
+/// Otherwise behaves like [`encode`] - see its docs for `padded` and
+/// `alphabet`. Use [`wrapped_encoded_len`] on top of [`encoded_len`] to
+/// size `out`.
+///
+/// # Errors
+/// * If `padded` is `false` and input length is not a multiple of 3 bytes
+/// * If output length does not match [`wrapped_encoded_len`] for `data`,
+///   `padded`, `line_length`, and `line_ending`
+/// * If any invalid base64 characters are encountered
+///
+/// # Panics
+/// * If `line_length` is 0
+#[inline]
+pub fn encode_wrapped(
+    data: &[u8],
+    out: &mut [u8],
+    padded: bool,
+    alphabet: Alphabet,
+    line_length: usize,
+    line_ending: LineEnding,
+) -> Result<(), &'static str> {
+    let nin = data.len();
+
+    if !padded && nin % 3 != 0 {
+        return Err("Input data must be a multiple of 3 bytes");
+    }
+
+    let raw_len = encoded_len(nin, padded);
+    if out.len() != wrapped_encoded_len(raw_len, line_length, line_ending) {
+        return Err("Output data length should match wrapped_encoded_len(...)");
+    }
+
+    let nchunks = nin / 3;
+    let remainder = nin % 3;
+    let mut out_idx = 0;
+    let mut col = 0;
+
+    for j in 0..nchunks {
+        let d = &data[3 * j..3 * j + 3];
+        let expanded = split_bytes(d);
+        for i in 0..4 {
+            let c = index_to_char(expanded[i], alphabet)?;
+            put_wrapped(out, &mut out_idx, &mut col, c, line_length, line_ending);
+        }
+    }
+
+    // Encode the trailing 1- or 2-byte group, padding with `=` as needed.
+    match remainder {
+        0 => {}
+        1 => {
+            let b = data[nin - 1];
+            let c0 = index_to_char(b >> 2, alphabet)?;
+            let c1 = index_to_char((b & 0b00000011) << 4, alphabet)?;
+            put_wrapped(out, &mut out_idx, &mut col, c0, line_length, line_ending);
+            put_wrapped(out, &mut out_idx, &mut col, c1, line_length, line_ending);
+            put_wrapped(out, &mut out_idx, &mut col, b'=', line_length, line_ending);
+            put_wrapped(out, &mut out_idx, &mut col, b'=', line_length, line_ending);
+        }
+        2 => {
+            let b0 = data[nin - 2];
+            let b1 = data[nin - 1];
+            let c0 = index_to_char(b0 >> 2, alphabet)?;
+            let c1 = index_to_char((b0 & 0b00000011) << 4 | b1 >> 4, alphabet)?;
+            let c2 = index_to_char((b1 & 0b00001111) << 2, alphabet)?;
+            put_wrapped(out, &mut out_idx, &mut col, c0, line_length, line_ending);
+            put_wrapped(out, &mut out_idx, &mut col, c1, line_length, line_ending);
+            put_wrapped(out, &mut out_idx, &mut col, c2, line_length, line_ending);
+            put_wrapped(out, &mut out_idx, &mut col, b'=', line_length, line_ending);
+        }
+        _ => unreachable!("remainder of division by 3 is always 0, 1, or 2"),
+    }
+
+    // A final line shorter than `line_length` still needs its own line
+    // ending; `put_wrapped` only flushes one when `col` wraps exactly.
+    if col > 0 {
+        let ending = line_ending.as_bytes();
+        out[out_idx..out_idx + ending.len()].copy_from_slice(ending);
+    }
+
+    Ok(())
+}
+
+/// Decode a base64 slice by lookup table, transparently skipping `\r`
+/// and `\n` line breaks such as those inserted by [`encode_wrapped`].
+/// Otherwise behaves like [`decode`] - see its docs for `padded` and
+/// `alphabet`; `out` should be sized with [`decoded_len`] on `data` with
+/// line breaks excluded, or simply to the known unwrapped length.
+///
+/// Like [`decode`], failures are reported as a [`DecodeError`]; offsets
+/// are counted against the unwrapped input, i.e. as if the skipped `\r`
+/// and `\n` bytes were never there.
+///
+/// # Errors
+/// * [`DecodeError::InvalidLength`] if the input, once line breaks are
+///   stripped, is not a multiple of 4 bytes, or if the output length
+///   doesn't match the unwrapped, padding-adjusted length
+/// * [`DecodeError::InvalidByte`] if any invalid base64 characters are
+///   encountered
+/// * [`DecodeError::InvalidLastSymbol`] if `padded` is `true` and the
+///   unused bits of the final symbol are nonzero
+#[inline]
+pub fn decode_wrapped(
+    data: &[u8],
+    out: &mut [u8],
+    padded: bool,
+    alphabet: Alphabet,
+) -> Result<(), DecodeError> {
+    let significant = || data.iter().copied().filter(|&b| b != b'\r' && b != b'\n');
+
+    let mut nin = 0;
+    let mut last_two = [0_u8; 2];
+    for b in significant() {
+        last_two[0] = last_two[1];
+        last_two[1] = b;
+        nin += 1;
+    }
+
+    if nin % 4 != 0 {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let nblocks = nin / 4;
+    let pad = if !padded || nin == 0 {
+        0
+    } else if last_two[1] == b'=' {
+        if last_two[0] == b'=' {
+            2
+        } else {
+            1
+        }
+    } else {
+        0
+    };
+
+    if out.len() != 3 * nblocks - pad {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let full_blocks = nblocks - if pad == 0 { 0 } else { 1 };
+    let mut converted = [0_u8; 4];
+    let mut group = [0_u8; 4];
+    let mut group_len = 0;
+    let mut group_idx = 0;
+
+    for b in significant() {
+        group[group_len] = b;
+        group_len += 1;
+
+        if group_len < 4 {
+            continue;
+        }
+        group_len = 0;
+
+        if group_idx < full_blocks {
+            for i in 0..4 {
+                let offset = 4 * group_idx + i;
+                converted[i] = char_to_index(group[i], alphabet)
+                    .map_err(|_| DecodeError::InvalidByte { offset, byte: group[i] })?;
+            }
+            let combined: [u8; 3] = combine_bytes(&converted);
+            out[3 * group_idx..3 * group_idx + 3].copy_from_slice(&combined);
+        } else {
+            let base_offset = 4 * full_blocks;
+            let c0 = char_to_index(group[0], alphabet).map_err(|_| DecodeError::InvalidByte {
+                offset: base_offset,
+                byte: group[0],
+            })?;
+            let c1 = char_to_index(group[1], alphabet).map_err(|_| DecodeError::InvalidByte {
+                offset: base_offset + 1,
+                byte: group[1],
+            })?;
+            let o = &mut out[3 * full_blocks..];
+
+            if pad == 1 {
+                let c2 = char_to_index(group[2], alphabet).map_err(|_| DecodeError::InvalidByte {
+                    offset: base_offset + 2,
+                    byte: group[2],
+                })?;
+                if c2 & 0b00000011 != 0 {
+                    return Err(DecodeError::InvalidLastSymbol {
+                        offset: base_offset + 2,
+                        byte: group[2],
+                    });
+                }
+                let combined = combine_bytes(&[c0, c1, c2, 0]);
+                o[0] = combined[0];
+                o[1] = combined[1];
+            } else {
+                if c1 & 0b00001111 != 0 {
+                    return Err(DecodeError::InvalidLastSymbol {
+                        offset: base_offset + 1,
+                        byte: group[1],
+                    });
+                }
+                let combined = combine_bytes(&[c0, c1, 0, 0]);
+                o[0] = combined[0];
+            }
+        }
+
+        group_idx += 1;
     }
 
     Ok(())
@@ -140,9 +1290,18 @@ mod tests {
 
     #[test]
     fn test_character_mapping() {
-        // Check that the char mapping produces is properly invertible
-        for i in 0..63_u8 {
-            assert_eq!(char_to_index(index_to_char(i).unwrap()).unwrap(), i);
+        // Check that the char mapping produces is properly invertible,
+        // for every alphabet.
+        for alphabet in [
+            Alphabet::Standard,
+            Alphabet::UrlSafe,
+            Alphabet::Bcrypt,
+            Alphabet::Crypt,
+        ] {
+            for i in 0..63_u8 {
+                let c = index_to_char(i, alphabet).unwrap();
+                assert_eq!(char_to_index(c, alphabet).unwrap(), i);
+            }
         }
     }
 
@@ -159,10 +1318,254 @@ mod tests {
         let input_de_buf = &mut [0_u8; 258];
         let output_ser_buf = &mut [0_u8; 344];
 
-        encode(input, output_ser_buf).unwrap();
-        decode(output, input_de_buf).unwrap();
+        encode(input, output_ser_buf, false, Alphabet::Standard).unwrap();
+        decode(output, input_de_buf, false, Alphabet::Standard).unwrap();
 
         assert_eq!(input, input_de_buf);
         assert_eq!(output, output_ser_buf);
     }
+
+    #[test]
+    fn test_padded_one_trailing_byte() {
+        let input = b"fo";
+        let out = &mut [0_u8; 4];
+        assert_eq!(encoded_len(input.len(), true), 4);
+        encode(input, out, true, Alphabet::Standard).unwrap();
+        assert_eq!(out, b"Zm8=");
+
+        let decoded_len = decoded_len(out, true).unwrap();
+        assert_eq!(decoded_len, input.len());
+        let round_trip = &mut [0_u8; 2];
+        decode(out, round_trip, true, Alphabet::Standard).unwrap();
+        assert_eq!(round_trip, input);
+    }
+
+    #[test]
+    fn test_padded_two_trailing_bytes() {
+        let input = b"f";
+        let out = &mut [0_u8; 4];
+        assert_eq!(encoded_len(input.len(), true), 4);
+        encode(input, out, true, Alphabet::Standard).unwrap();
+        assert_eq!(out, b"Zg==");
+
+        let decoded_len = decoded_len(out, true).unwrap();
+        assert_eq!(decoded_len, input.len());
+        let round_trip = &mut [0_u8; 1];
+        decode(out, round_trip, true, Alphabet::Standard).unwrap();
+        assert_eq!(round_trip, input);
+    }
+
+    #[test]
+    fn test_padded_rejects_nonzero_padding_bits() {
+        // "Zh==" decodes the same leading byte as "Zg==" would, but its
+        // final symbol ('h') has nonzero low bits that get discarded -
+        // a real encoder never produces this.
+        let corrupted = b"Zh==";
+        let out = &mut [0_u8; 1];
+        assert_eq!(
+            decode(corrupted, out, true, Alphabet::Standard),
+            Err(DecodeError::InvalidLastSymbol {
+                offset: 1,
+                byte: b'h'
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_reports_invalid_byte_position() {
+        let data = b"AA!A";
+        let out = &mut [0_u8; 3];
+        assert_eq!(
+            decode(data, out, false, Alphabet::Standard),
+            Err(DecodeError::InvalidByte {
+                offset: 2,
+                byte: b'!'
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_reports_invalid_byte_position_past_simd_block() {
+        // 32 bytes is 8 chunks, i.e. two full SIMD blocks on platforms
+        // where the vector path is compiled in - make sure the bad byte
+        // in the second block is still pinpointed precisely once the
+        // scalar loop takes back over.
+        let mut data = *b"QUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFB";
+        data[20] = b'!';
+        let out = &mut [0_u8; 24];
+        assert_eq!(
+            decode(data.as_ref(), out, false, Alphabet::Standard),
+            Err(DecodeError::InvalidByte {
+                offset: 20,
+                byte: b'!'
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_many_simd_blocks() {
+        // 120 bytes is 40 chunks, i.e. ten full SIMD blocks on platforms
+        // where the vector path is compiled in, with no scalar remainder.
+        let input: &mut [u8; 120] = &mut [0_u8; 120];
+        for (i, b) in input.iter_mut().enumerate() {
+            *b = (i * 7 % 256) as u8;
+        }
+        let out = &mut [0_u8; 160];
+        encode(input, out, false, Alphabet::Standard).unwrap();
+
+        let round_trip = &mut [0_u8; 120];
+        decode(out, round_trip, false, Alphabet::Standard).unwrap();
+        assert_eq!(round_trip, input);
+    }
+
+    #[test]
+    fn test_url_safe_alphabet() {
+        // 0xfb 0xff 0xbf encodes to "+/+/" in the standard alphabet
+        let input: &[u8] = &[0xfb, 0xff, 0xbf];
+        let out = &mut [0_u8; 4];
+        encode(input, out, false, Alphabet::UrlSafe).unwrap();
+        assert_eq!(out, b"-_-_");
+
+        let round_trip = &mut [0_u8; 3];
+        decode(out, round_trip, false, Alphabet::UrlSafe).unwrap();
+        assert_eq!(round_trip, input);
+    }
+
+    #[test]
+    fn test_bcrypt_and_crypt_alphabets() {
+        let input: &mut [u8; 258] = &mut [0_u8; 258];
+        for i in 0..258 {
+            input[i] = (i % 256) as u8;
+        }
+
+        for alphabet in [Alphabet::Bcrypt, Alphabet::Crypt] {
+            let out = &mut [0_u8; 344];
+            encode(input, out, false, alphabet).unwrap();
+            let round_trip = &mut [0_u8; 258];
+            decode(out, round_trip, false, alphabet).unwrap();
+            assert_eq!(round_trip, input);
+        }
+    }
+
+    #[test]
+    fn test_ct_matches_table_driven() {
+        // The constant-time path must agree with the branching path
+        // byte-for-byte, padded and unpadded.
+        let input: &mut [u8; 258] = &mut [0_u8; 258];
+        for i in 0..258 {
+            input[i] = (i % 256) as u8;
+        }
+
+        let ct_out = &mut [0_u8; 344];
+        let table_out = &mut [0_u8; 344];
+        encode_ct(input, ct_out, false).unwrap();
+        encode(input, table_out, false, Alphabet::Standard).unwrap();
+        assert_eq!(ct_out, table_out);
+
+        let ct_round_trip = &mut [0_u8; 258];
+        let table_round_trip = &mut [0_u8; 258];
+        decode_ct(ct_out, ct_round_trip, false).unwrap();
+        decode(table_out, table_round_trip, false, Alphabet::Standard).unwrap();
+        assert_eq!(ct_round_trip, input);
+        assert_eq!(table_round_trip, input);
+
+        let ct_padded = &mut [0_u8; 4];
+        let table_padded = &mut [0_u8; 4];
+        encode_ct(b"fo", ct_padded, true).unwrap();
+        encode(b"fo", table_padded, true, Alphabet::Standard).unwrap();
+        assert_eq!(ct_padded, table_padded);
+
+        let ct_padded_round_trip = &mut [0_u8; 2];
+        decode_ct(ct_padded, ct_padded_round_trip, true).unwrap();
+        assert_eq!(ct_padded_round_trip, b"fo");
+    }
+
+    #[test]
+    fn test_ct_rejects_invalid_char() {
+        let out = &mut [0_u8; 3];
+        assert!(decode_ct(b"AA!=", out, false).is_err());
+    }
+
+    #[test]
+    fn test_wrapped_encode_lf() {
+        let input = b"foobar";
+        let raw_len = encoded_len(input.len(), false);
+        let wrapped_len = wrapped_encoded_len(raw_len, 4, LineEnding::Lf);
+        assert_eq!(wrapped_len, 10);
+
+        let out = &mut [0_u8; 10];
+        encode_wrapped(input, out, false, Alphabet::Standard, 4, LineEnding::Lf).unwrap();
+        assert_eq!(out, b"Zm9v\nYmFy\n");
+    }
+
+    #[test]
+    fn test_wrapped_decode_skips_crlf() {
+        let wrapped = b"Zm9v\r\nYmFy\r\n";
+        let out = &mut [0_u8; 6];
+        decode_wrapped(wrapped, out, false, Alphabet::Standard).unwrap();
+        assert_eq!(out, b"foobar");
+    }
+
+    #[test]
+    fn test_wrapped_round_trip_pem_line_length() {
+        // 48 bytes encodes to exactly one 64-character PEM line, so the
+        // wrapped form gets a single trailing line break.
+        let input: &mut [u8; 48] = &mut [0_u8; 48];
+        for i in 0..48 {
+            input[i] = (i % 256) as u8;
+        }
+
+        let raw_len = encoded_len(input.len(), false);
+        let wrapped_len = wrapped_encoded_len(raw_len, 64, LineEnding::Lf);
+        assert_eq!(wrapped_len, 65);
+
+        let out = &mut [0_u8; 65];
+        encode_wrapped(input, out, false, Alphabet::Standard, 64, LineEnding::Lf).unwrap();
+        assert_eq!(out[64], b'\n');
+
+        let round_trip = &mut [0_u8; 48];
+        decode_wrapped(out, round_trip, false, Alphabet::Standard).unwrap();
+        assert_eq!(round_trip, input);
+    }
+
+    #[test]
+    fn test_wrapped_encode_flushes_partial_final_line() {
+        // 51 bytes encodes to 68 characters, which is not an exact
+        // multiple of the 64-character PEM line length, so the final
+        // line is short and must still be terminated.
+        let input: &mut [u8; 51] = &mut [0_u8; 51];
+        for i in 0..51 {
+            input[i] = (i % 256) as u8;
+        }
+
+        let raw_len = encoded_len(input.len(), false);
+        let wrapped_len = wrapped_encoded_len(raw_len, 64, LineEnding::Lf);
+        assert_eq!(wrapped_len, 70);
+
+        let out = &mut [0xff_u8; 70];
+        encode_wrapped(input, out, false, Alphabet::Standard, 64, LineEnding::Lf).unwrap();
+        assert_eq!(out[64], b'\n');
+        assert_eq!(out[69], b'\n');
+        assert!(out.iter().all(|&b| b != 0xff));
+
+        let round_trip = &mut [0_u8; 51];
+        decode_wrapped(out, round_trip, false, Alphabet::Standard).unwrap();
+        assert_eq!(round_trip, input);
+    }
+
+    #[test]
+    fn test_wrapped_decode_reports_unwrapped_offset() {
+        // "Zm9v\r\nYm!v\r\n" has an invalid byte ('!') at unwrapped offset
+        // 6, even though its position counting the "\r\n" line breaks in
+        // the wrapped buffer would be 8.
+        let wrapped = b"Zm9v\r\nYm!v\r\n";
+        let out = &mut [0_u8; 6];
+        assert_eq!(
+            decode_wrapped(wrapped, out, false, Alphabet::Standard),
+            Err(DecodeError::InvalidByte {
+                offset: 6,
+                byte: b'!'
+            })
+        );
+    }
 }